@@ -0,0 +1,58 @@
+//! Command-line interface definition.
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+/// Check your Claude usage limits from the terminal.
+#[derive(Parser, Debug)]
+#[command(name = "claude-usage", version, about, long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Width of the usage bar, in characters. Overrides the config file.
+    #[arg(long, global = true)]
+    pub bar_width: Option<usize>,
+
+    /// Utilization percent at which a window is shown as elevated. Overrides the config file.
+    #[arg(long, global = true)]
+    pub elevated_threshold: Option<f64>,
+
+    /// Utilization percent at which a window is shown as critical. Overrides the config file.
+    #[arg(long, global = true)]
+    pub critical_threshold: Option<f64>,
+
+    /// Disable colored output.
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Only print the given window(s); repeatable. Overrides the config file.
+    #[arg(long = "window", global = true, value_enum)]
+    pub windows: Vec<Window>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Render the usage bars once (default).
+    Show {
+        /// Print plain, uncolored text instead of bars (for logs/pipes).
+        #[arg(long, short)]
+        plain: bool,
+    },
+    /// Poll usage on an interval and redraw in place.
+    Watch {
+        /// Seconds between refreshes. Overrides the config file.
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+    /// Emit machine-readable JSON and exit.
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    #[value(name = "5h")]
+    FiveHour,
+    #[value(name = "7d")]
+    SevenDay,
+    Opus,
+}
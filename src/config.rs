@@ -0,0 +1,242 @@
+//! User-overridable settings, loaded from `~/.config/claude-usage/config.toml`.
+//!
+//! Precedence: CLI flags > config file > built-in defaults. Everything the
+//! rendering code needs — thresholds, bar width, windows, watch interval,
+//! default mode — is resolved once into a single [`Settings`] struct instead
+//! of being read from scattered literals and CLI fields.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::cli::{Cli, Command, Window};
+
+const DEFAULT_BAR_WIDTH: usize = 28;
+const DEFAULT_ELEVATED_THRESHOLD: f64 = 70.0;
+const DEFAULT_CRITICAL_THRESHOLD: f64 = 90.0;
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    elevated_threshold: Option<f64>,
+    critical_threshold: Option<f64>,
+    bar_width: Option<usize>,
+    mode: Option<String>,
+    watch_interval: Option<String>,
+    windows: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DefaultMode {
+    Show,
+    Watch,
+    Json,
+}
+
+impl DefaultMode {
+    fn into_command(self) -> Command {
+        match self {
+            DefaultMode::Show => Command::Show { plain: false },
+            DefaultMode::Watch => Command::Watch { interval: None },
+            DefaultMode::Json => Command::Json,
+        }
+    }
+}
+
+/// Fully-resolved settings the renderer reads from.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub bar_width: usize,
+    pub elevated_threshold: f64,
+    pub critical_threshold: f64,
+    pub watch_interval: Duration,
+    pub windows: Vec<Window>,
+    pub default_mode: DefaultMode,
+}
+
+impl Settings {
+    /// Whether `window` should be rendered given the resolved window filter.
+    pub fn shows(&self, window: Window) -> bool {
+        self.windows.is_empty() || self.windows.contains(&window)
+    }
+
+    /// The command to run when the user didn't name one on the CLI.
+    pub fn default_command(&self) -> Command {
+        self.default_mode.into_command()
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("claude-usage").join("config.toml"))
+}
+
+fn load_file() -> Result<FileConfig> {
+    let Some(path) = config_path() else {
+        return Ok(FileConfig::default());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => toml::from_str(&raw).with_context(|| format!("Could not parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FileConfig::default()),
+        Err(e) => Err(e).with_context(|| format!("Could not read {}", path.display())),
+    }
+}
+
+/// Parses durations like `"30s"`, `"5m"`, `"2h"` into a real [`Duration`].
+fn parse_duration(raw: &str) -> Result<Duration> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        bail!("Invalid watch_interval '' in config — expected e.g. '30s', '5m', '2h'");
+    }
+
+    let split_at = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (value, unit) = raw.split_at(split_at);
+    if value.is_empty() {
+        bail!("Invalid watch_interval '{raw}' in config — missing a number");
+    }
+    let value: u64 = value
+        .parse()
+        .with_context(|| format!("Invalid watch_interval '{raw}' in config"))?;
+
+    let multiplier: u64 = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        other => bail!("Unknown duration unit '{other}' in '{raw}' — expected s/m/h/d"),
+    };
+    let secs = value
+        .checked_mul(multiplier)
+        .with_context(|| format!("watch_interval '{raw}' in config is too large"))?;
+    Ok(Duration::from_secs(secs))
+}
+
+fn parse_window(raw: &str) -> Result<Window> {
+    match raw {
+        "5h" => Ok(Window::FiveHour),
+        "7d" => Ok(Window::SevenDay),
+        "opus" => Ok(Window::Opus),
+        other => bail!("Unknown window '{other}' in config — expected 5h/7d/opus"),
+    }
+}
+
+fn parse_mode(raw: &str) -> Result<DefaultMode> {
+    match raw {
+        "show" => Ok(DefaultMode::Show),
+        "watch" => Ok(DefaultMode::Watch),
+        "json" => Ok(DefaultMode::Json),
+        other => bail!("Unknown mode '{other}' in config — expected show/watch/json"),
+    }
+}
+
+/// Resolves settings from CLI flags, the config file, and built-in defaults,
+/// in that order of precedence.
+pub fn resolve(cli: &Cli) -> Result<Settings> {
+    let file = load_file()?;
+
+    let bar_width = cli.bar_width.or(file.bar_width).unwrap_or(DEFAULT_BAR_WIDTH);
+    let elevated_threshold = cli
+        .elevated_threshold
+        .or(file.elevated_threshold)
+        .unwrap_or(DEFAULT_ELEVATED_THRESHOLD);
+    let critical_threshold = cli
+        .critical_threshold
+        .or(file.critical_threshold)
+        .unwrap_or(DEFAULT_CRITICAL_THRESHOLD);
+
+    let watch_interval = match &file.watch_interval {
+        Some(raw) => parse_duration(raw)?,
+        None => DEFAULT_WATCH_INTERVAL,
+    };
+
+    let windows = if !cli.windows.is_empty() {
+        cli.windows.clone()
+    } else if let Some(raw) = &file.windows {
+        raw.iter().map(|w| parse_window(w)).collect::<Result<_>>()?
+    } else {
+        Vec::new()
+    };
+
+    let default_mode = match &file.mode {
+        Some(raw) => parse_mode(raw)?,
+        None => DefaultMode::Show,
+    };
+
+    Ok(Settings {
+        bar_width,
+        elevated_threshold,
+        critical_threshold,
+        watch_interval,
+        windows,
+        default_mode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_plain_seconds() {
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(5 * 60));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(2 * 60 * 60));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(24 * 60 * 60));
+    }
+
+    #[test]
+    fn parse_duration_trims_whitespace() {
+        assert_eq!(parse_duration("  5m  ").unwrap(), Duration::from_secs(5 * 60));
+    }
+
+    #[test]
+    fn parse_duration_rejects_empty_string() {
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_missing_number() {
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_unknown_unit() {
+        assert!(parse_duration("5x").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        assert!(parse_duration("99999999999999999999d").is_err());
+    }
+
+    #[test]
+    fn parse_window_known_values() {
+        assert!(matches!(parse_window("5h").unwrap(), Window::FiveHour));
+        assert!(matches!(parse_window("7d").unwrap(), Window::SevenDay));
+        assert!(matches!(parse_window("opus").unwrap(), Window::Opus));
+    }
+
+    #[test]
+    fn parse_window_rejects_unknown_value() {
+        assert!(parse_window("30d").is_err());
+        assert!(parse_window("").is_err());
+    }
+
+    #[test]
+    fn parse_mode_known_values() {
+        assert!(matches!(parse_mode("show").unwrap(), DefaultMode::Show));
+        assert!(matches!(parse_mode("watch").unwrap(), DefaultMode::Watch));
+        assert!(matches!(parse_mode("json").unwrap(), DefaultMode::Json));
+    }
+
+    #[test]
+    fn parse_mode_rejects_unknown_value() {
+        assert!(parse_mode("plain").is_err());
+        assert!(parse_mode("").is_err());
+    }
+}
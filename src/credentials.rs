@@ -0,0 +1,235 @@
+//! Reading Claude Code's stored OAuth credentials across desktop platforms.
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OAuthCredentials {
+    #[serde(rename = "claudeAiOauth")]
+    pub claude_ai_oauth: OAuthToken,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OAuthToken {
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: Option<String>,
+    /// Expiry as milliseconds since the Unix epoch.
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<i64>,
+    #[serde(rename = "subscriptionType")]
+    pub subscription_type: Option<String>,
+}
+
+impl OAuthToken {
+    /// Whether the access token has expired (or is about to, within a
+    /// small safety margin), and a refresh should be attempted first.
+    pub fn is_expired(&self) -> bool {
+        const LEEWAY_MS: i64 = 30_000;
+        match self.expires_at {
+            Some(expires_at) => Utc::now().timestamp_millis() + LEEWAY_MS >= expires_at,
+            None => false,
+        }
+    }
+}
+
+fn parse_credentials(raw: &str) -> Result<OAuthToken> {
+    let creds: OAuthCredentials = serde_json::from_str(raw.trim())
+        .context("Could not parse Claude Code credentials")?;
+    Ok(creds.claude_ai_oauth)
+}
+
+/// A platform-specific place Claude Code's OAuth token can be read from
+/// (and, where supported, written back to after a refresh).
+pub trait CredentialSource {
+    fn read_token(&self) -> Result<OAuthToken>;
+
+    fn write_token(&self, _token: &OAuthToken) -> Result<()> {
+        bail!("Writing refreshed credentials back is not supported on this platform")
+    }
+}
+
+/// macOS Keychain, via the `security` CLI.
+#[cfg(target_os = "macos")]
+pub struct MacKeychain;
+
+#[cfg(target_os = "macos")]
+impl CredentialSource for MacKeychain {
+    fn read_token(&self) -> Result<OAuthToken> {
+        let output = std::process::Command::new("security")
+            .args(["find-generic-password", "-s", "Claude Code-credentials", "-w"])
+            .output()
+            .context("Failed to run 'security' command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "Could not read Claude Code credentials from Keychain.\n\
+                 Make sure Claude Code is installed and you've logged in.\n\
+                 Error: {stderr}"
+            );
+        }
+
+        let raw = String::from_utf8(output.stdout).context("Keychain output was not valid UTF-8")?;
+        parse_credentials(&raw)
+    }
+
+    fn write_token(&self, token: &OAuthToken) -> Result<()> {
+        let creds = OAuthCredentials {
+            claude_ai_oauth: token.clone(),
+        };
+        let raw = serde_json::to_string(&creds).context("Could not serialize refreshed credentials")?;
+
+        let status = std::process::Command::new("security")
+            .args([
+                "add-generic-password",
+                "-U",
+                "-s",
+                "Claude Code-credentials",
+                "-w",
+                &raw,
+            ])
+            .status()
+            .context("Failed to run 'security' command")?;
+
+        if !status.success() {
+            bail!("Could not write refreshed credentials back to Keychain");
+        }
+        Ok(())
+    }
+}
+
+/// Linux Secret Service (via `secret-tool`, part of libsecret), falling back to
+/// Claude Code's plaintext credentials file.
+#[cfg(target_os = "linux")]
+pub struct LinuxSecretService;
+
+#[cfg(target_os = "linux")]
+fn read_from_secret_tool() -> Result<OAuthToken> {
+    let output = std::process::Command::new("secret-tool")
+        .args(["lookup", "service", "Claude Code-credentials"])
+        .output()
+        .context("Failed to run 'secret-tool' — is libsecret-tools installed?")?;
+
+    if !output.status.success() {
+        bail!("secret-tool could not find Claude Code credentials in the Secret Service");
+    }
+
+    let raw = String::from_utf8(output.stdout).context("secret-tool output was not valid UTF-8")?;
+    parse_credentials(&raw)
+}
+
+#[cfg(target_os = "linux")]
+fn read_from_credentials_file() -> Result<OAuthToken> {
+    let path = dirs::home_dir()
+        .context("Could not determine home directory")?
+        .join(".config/claude/.credentials.json");
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+    parse_credentials(&raw)
+}
+
+#[cfg(target_os = "linux")]
+impl CredentialSource for LinuxSecretService {
+    fn read_token(&self) -> Result<OAuthToken> {
+        read_from_secret_tool()
+            .or_else(|_| read_from_credentials_file())
+            .context(
+                "Could not read Claude Code credentials from the Secret Service or \
+                 ~/.config/claude/.credentials.json.\n\
+                 Make sure Claude Code is installed and you've logged in.",
+            )
+    }
+}
+
+/// Windows Credential Manager, via the `CredReadW` Win32 API.
+#[cfg(target_os = "windows")]
+pub struct WindowsCredentialManager;
+
+#[cfg(target_os = "windows")]
+impl CredentialSource for WindowsCredentialManager {
+    fn read_token(&self) -> Result<OAuthToken> {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use windows::core::PCWSTR;
+        use windows::Win32::Security::Credentials::{CredFree, CredReadW, CRED_TYPE_GENERIC};
+
+        let target: Vec<u16> = OsStr::new("Claude Code-credentials")
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let mut raw = String::new();
+        unsafe {
+            let mut pcred = std::ptr::null_mut();
+            CredReadW(PCWSTR(target.as_ptr()), CRED_TYPE_GENERIC, 0, &mut pcred)
+                .context("Could not read Claude Code credentials from Credential Manager")?;
+            let cred = &*pcred;
+            let blob = std::slice::from_raw_parts(cred.CredentialBlob, cred.CredentialBlobSize as usize);
+            raw = String::from_utf8_lossy(blob).into_owned();
+            CredFree(pcred as *const _);
+        }
+
+        parse_credentials(&raw)
+    }
+}
+
+/// Selects the credential backend for the platform we're running on.
+pub fn default_source() -> Box<dyn CredentialSource> {
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(MacKeychain)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(LinuxSecretService)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(WindowsCredentialManager)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        compile_error!("claude-usage supports macOS, Linux, and Windows only");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_with_expiry(expires_at: Option<i64>) -> OAuthToken {
+        OAuthToken {
+            access_token: "token".to_string(),
+            refresh_token: None,
+            expires_at,
+            subscription_type: None,
+        }
+    }
+
+    #[test]
+    fn is_expired_with_no_expiry_is_never_expired() {
+        assert!(!token_with_expiry(None).is_expired());
+    }
+
+    #[test]
+    fn is_expired_in_the_past() {
+        let expires_at = Utc::now().timestamp_millis() - 60_000;
+        assert!(token_with_expiry(Some(expires_at)).is_expired());
+    }
+
+    #[test]
+    fn is_expired_within_leeway_window() {
+        // Still valid by a few seconds, but inside the 30s safety margin.
+        let expires_at = Utc::now().timestamp_millis() + 10_000;
+        assert!(token_with_expiry(Some(expires_at)).is_expired());
+    }
+
+    #[test]
+    fn is_expired_comfortably_in_the_future() {
+        let expires_at = Utc::now().timestamp_millis() + 60_000;
+        assert!(!token_with_expiry(Some(expires_at)).is_expired());
+    }
+}
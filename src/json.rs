@@ -0,0 +1,149 @@
+//! Structured JSON output, for scripts and status bars (tmux/polybar/waybar)
+//! that want to consume usage without scraping colored terminal text.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cli::Window;
+use crate::config::Settings;
+use crate::credentials::OAuthToken;
+use crate::{UsageResponse, UsageWindow};
+
+#[derive(Debug, Serialize)]
+pub struct JsonUsage {
+    pub plan: String,
+    pub five_hour: Option<JsonWindow>,
+    pub seven_day: Option<JsonWindow>,
+    pub seven_day_opus: Option<JsonWindow>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonWindow {
+    pub utilization: f64,
+    pub resets_at: Option<String>,
+    pub resets_in_seconds: Option<i64>,
+    pub severity: Severity,
+}
+
+/// Same thresholds (resolved from CLI/config/defaults) used to color the
+/// bars in `print_window`.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    Elevated,
+    Critical,
+}
+
+impl Severity {
+    fn from_utilization(pct: f64, settings: &Settings) -> Self {
+        if pct >= settings.critical_threshold {
+            Severity::Critical
+        } else if pct >= settings.elevated_threshold {
+            Severity::Elevated
+        } else {
+            Severity::Ok
+        }
+    }
+}
+
+fn resets_in_seconds(resets_at: &Option<String>) -> Option<i64> {
+    let dt: DateTime<Utc> = resets_at.as_deref()?.parse().ok()?;
+    Some(dt.signed_duration_since(Utc::now()).num_seconds())
+}
+
+fn to_json_window(w: &UsageWindow, settings: &Settings) -> JsonWindow {
+    JsonWindow {
+        utilization: w.utilization,
+        resets_at: w.resets_at.clone(),
+        resets_in_seconds: resets_in_seconds(&w.resets_at),
+        severity: Severity::from_utilization(w.utilization, settings),
+    }
+}
+
+/// Serializes `usage` to stdout as a single JSON object and exits 0.
+/// Non-zero exit codes are reserved for actual errors (see `main`).
+pub fn render(settings: &Settings, token: &OAuthToken, usage: &UsageResponse) -> anyhow::Result<()> {
+    let out = JsonUsage {
+        plan: token
+            .subscription_type
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+        five_hour: settings
+            .shows(Window::FiveHour)
+            .then(|| usage.five_hour.as_ref().map(|w| to_json_window(w, settings)))
+            .flatten(),
+        seven_day: settings
+            .shows(Window::SevenDay)
+            .then(|| usage.seven_day.as_ref().map(|w| to_json_window(w, settings)))
+            .flatten(),
+        seven_day_opus: settings
+            .shows(Window::Opus)
+            .then(|| usage.seven_day_opus.as_ref().map(|w| to_json_window(w, settings)))
+            .flatten(),
+    };
+
+    serde_json::to_writer(std::io::stdout(), &out)?;
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DefaultMode;
+    use std::time::Duration;
+
+    fn settings(elevated_threshold: f64, critical_threshold: f64) -> Settings {
+        Settings {
+            bar_width: 28,
+            elevated_threshold,
+            critical_threshold,
+            watch_interval: Duration::from_secs(60),
+            windows: Vec::new(),
+            default_mode: DefaultMode::Show,
+        }
+    }
+
+    #[test]
+    fn severity_from_utilization_below_elevated_is_ok() {
+        let settings = settings(70.0, 90.0);
+        assert!(matches!(Severity::from_utilization(50.0, &settings), Severity::Ok));
+    }
+
+    #[test]
+    fn severity_from_utilization_at_elevated_threshold() {
+        let settings = settings(70.0, 90.0);
+        assert!(matches!(Severity::from_utilization(70.0, &settings), Severity::Elevated));
+    }
+
+    #[test]
+    fn severity_from_utilization_at_critical_threshold() {
+        let settings = settings(70.0, 90.0);
+        assert!(matches!(Severity::from_utilization(90.0, &settings), Severity::Critical));
+    }
+
+    #[test]
+    fn resets_in_seconds_none_for_missing_timestamp() {
+        assert_eq!(resets_in_seconds(&None), None);
+    }
+
+    #[test]
+    fn resets_in_seconds_none_for_unparsable_timestamp() {
+        assert_eq!(resets_in_seconds(&Some("not a timestamp".to_string())), None);
+    }
+
+    #[test]
+    fn resets_in_seconds_positive_for_future_timestamp() {
+        let resets_at = (Utc::now() + chrono::Duration::seconds(120)).to_rfc3339();
+        let secs = resets_in_seconds(&Some(resets_at)).expect("should parse");
+        assert!((110..=120).contains(&secs));
+    }
+
+    #[test]
+    fn resets_in_seconds_negative_for_past_timestamp() {
+        let resets_at = (Utc::now() - chrono::Duration::seconds(60)).to_rfc3339();
+        let secs = resets_in_seconds(&Some(resets_at)).expect("should parse");
+        assert!(secs <= -59);
+    }
+}
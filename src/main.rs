@@ -1,64 +1,33 @@
 use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Local, Utc};
+use clap::Parser;
 use colored::Colorize;
 use reqwest::blocking::Client;
 use serde::Deserialize;
-use std::process::Command;
 
-// ─── API Response Types ───────────────────────────────────────────────────────
+mod cli;
+mod config;
+mod credentials;
+mod json;
+mod oauth;
 
-#[derive(Debug, Deserialize)]
-struct UsageWindow {
-    utilization: f64,        // 0–100 percent
-    resets_at: Option<String>,
-}
+use cli::{Cli, Window};
+use config::Settings;
+use credentials::{CredentialSource, OAuthToken};
 
-#[derive(Debug, Deserialize)]
-struct UsageResponse {
-    five_hour: Option<UsageWindow>,
-    seven_day: Option<UsageWindow>,
-    seven_day_opus: Option<UsageWindow>,
-}
-
-// ─── Keychain credential reading (macOS) ─────────────────────────────────────
+// ─── API Response Types ───────────────────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
-struct OAuthCredentials {
-    #[serde(rename = "claudeAiOauth")]
-    claude_ai_oauth: OAuthToken,
+pub struct UsageWindow {
+    pub utilization: f64,        // 0–100 percent
+    pub resets_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct OAuthToken {
-    #[serde(rename = "accessToken")]
-    access_token: String,
-    #[serde(rename = "subscriptionType")]
-    subscription_type: Option<String>,
-}
-
-fn get_token_from_keychain() -> Result<OAuthToken> {
-    let output = Command::new("security")
-        .args(["find-generic-password", "-s", "Claude Code-credentials", "-w"])
-        .output()
-        .context("Failed to run 'security' command — are you on macOS?")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!(
-            "Could not read Claude Code credentials from Keychain.\n\
-             Make sure Claude Code is installed and you've logged in.\n\
-             Error: {stderr}"
-        );
-    }
-
-    let raw = String::from_utf8(output.stdout)
-        .context("Keychain output was not valid UTF-8")?;
-    let raw = raw.trim();
-
-    let creds: OAuthCredentials = serde_json::from_str(raw)
-        .context("Could not parse Claude Code credentials from Keychain")?;
-
-    Ok(creds.claude_ai_oauth)
+pub struct UsageResponse {
+    pub five_hour: Option<UsageWindow>,
+    pub seven_day: Option<UsageWindow>,
+    pub seven_day_opus: Option<UsageWindow>,
 }
 
 // ─── API call ─────────────────────────────────────────────────────────────────
@@ -76,7 +45,7 @@ fn fetch_usage(token: &str) -> Result<UsageResponse> {
 
     let status = resp.status();
     if status == 401 {
-        bail!("Token expired or invalid — try logging out and back in with Claude Code:\n  claude logout && claude");
+        return Err(oauth::Unauthorized.into());
     }
     if !status.is_success() {
         let body = resp.text().unwrap_or_default();
@@ -86,18 +55,47 @@ fn fetch_usage(token: &str) -> Result<UsageResponse> {
     resp.json::<UsageResponse>().context("Failed to parse usage response")
 }
 
+/// Fetches usage, refreshing the access token first if it's expired and
+/// retrying once if the API itself reports the token as unauthorized. At most
+/// one refresh is attempted per call.
+fn fetch_usage_with_refresh(
+    source: &dyn CredentialSource,
+    mut token: OAuthToken,
+) -> Result<(UsageResponse, OAuthToken)> {
+    const RELOGIN_HINT: &str = "Token expired or invalid — try logging out and back in with Claude Code:\n  \
+                                claude logout && claude";
+
+    let refresh_attempted = token.is_expired();
+    if refresh_attempted {
+        if let Ok(refreshed) = oauth::refresh(source, &token) {
+            token = refreshed;
+        }
+    }
+
+    match fetch_usage(&token.access_token) {
+        Err(e) if oauth::is_unauthorized(&e) && refresh_attempted => Err(e.context(RELOGIN_HINT)),
+        Err(e) if oauth::is_unauthorized(&e) => {
+            let refreshed = oauth::refresh(source, &token).context(RELOGIN_HINT)?;
+            let usage = fetch_usage(&refreshed.access_token)?;
+            Ok((usage, refreshed))
+        }
+        Err(e) => Err(e),
+        Ok(usage) => Ok((usage, token)),
+    }
+}
+
 // ─── Display ─────────────────────────────────────────────────────────────────
 
-fn usage_bar(pct: f64, width: usize) -> colored::ColoredString {
+fn usage_bar(pct: f64, width: usize, elevated: f64, critical: f64) -> colored::ColoredString {
     let filled = ((pct / 100.0) * width as f64).round() as usize;
     let filled = filled.min(width);
     let empty = width - filled;
     let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
 
     // Colour by how full it is
-    if pct >= 90.0 {
+    if pct >= critical {
         bar.red().bold()
-    } else if pct >= 70.0 {
+    } else if pct >= elevated {
         bar.yellow()
     } else {
         bar.green()
@@ -113,7 +111,6 @@ fn format_reset(resets_at: &Option<String>) -> String {
         return ts.clone();
     };
     let local: DateTime<Local> = dt.into();
-    let now = Local::now();
     let diff = dt.signed_duration_since(Utc::now());
 
     let mins = diff.num_minutes();
@@ -136,17 +133,17 @@ fn format_reset(resets_at: &Option<String>) -> String {
     )
 }
 
-fn print_window(label: &str, window: &Option<UsageWindow>, bar_width: usize) {
+fn print_window(label: &str, window: &Option<UsageWindow>, settings: &Settings) {
     match window {
         None => {
             println!("  {:<18} {}", label, "not available".dimmed());
         }
         Some(w) => {
             let pct = w.utilization.min(100.0);
-            let bar = usage_bar(pct, bar_width);
-            let pct_str = if pct >= 90.0 {
+            let bar = usage_bar(pct, settings.bar_width, settings.elevated_threshold, settings.critical_threshold);
+            let pct_str = if pct >= settings.critical_threshold {
                 format!("{:5.1}%", pct).red().bold()
-            } else if pct >= 70.0 {
+            } else if pct >= settings.elevated_threshold {
                 format!("{:5.1}%", pct).yellow()
             } else {
                 format!("{:5.1}%", pct).green()
@@ -185,26 +182,69 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    let plain = std::env::args().any(|a| a == "--plain" || a == "-p");
+    let mut cli = Cli::parse();
+
+    if cli.no_color {
+        colored::control::set_override(false);
+    }
 
+    let settings = config::resolve(&cli)?;
+    let command = cli.command.take().unwrap_or_else(|| settings.default_command());
+    match command {
+        cli::Command::Show { plain } => show(&settings, plain),
+        cli::Command::Watch { interval } => {
+            watch(&settings, interval.unwrap_or(settings.watch_interval.as_secs()))
+        }
+        cli::Command::Json => json_output(&settings),
+    }
+}
+
+/// Fetches usage once and prints it as a single JSON object.
+fn json_output(settings: &Settings) -> Result<()> {
+    let source = credentials::default_source();
+    let token = source.read_token()?;
+    let (usage, token) = fetch_usage_with_refresh(source.as_ref(), token)?;
+    json::render(settings, &token, &usage)
+}
+
+fn show(settings: &Settings, plain: bool) -> Result<()> {
     if !plain {
         println!();
         print!("  {} Fetching usage data... ", "◆".cyan());
     }
 
-    let token = get_token_from_keychain()?;
-    let usage = fetch_usage(&token.access_token)?;
+    let source = credentials::default_source();
+    let token = source.read_token()?;
+    let (usage, token) = fetch_usage_with_refresh(source.as_ref(), token)?;
 
     if plain {
-        print_plain("5hr session", &usage.five_hour);
-        print_plain("7 day rolling", &usage.seven_day);
+        if settings.shows(Window::FiveHour) {
+            print_plain("5hr session", &usage.five_hour);
+        }
+        if settings.shows(Window::SevenDay) {
+            print_plain("7 day rolling", &usage.seven_day);
+        }
+        // Only show Opus row if it has data (Max plan), same as the non-plain path.
+        if settings.shows(Window::Opus) {
+            if let Some(ref opus) = usage.seven_day_opus {
+                if opus.utilization > 0.0 || opus.resets_at.is_some() {
+                    print_plain("7 day (opus)", &usage.seven_day_opus);
+                }
+            }
+        }
         return Ok(());
     }
 
     // Clear the "fetching" line
     print!("\r{}\r", " ".repeat(50));
 
-    // Header
+    render_usage(settings, &token, &usage);
+    Ok(())
+}
+
+/// Renders the header, usage bars, and summary hint for one snapshot of
+/// `usage`. Shared by the one-shot `show` render and the `watch` loop.
+fn render_usage(settings: &Settings, token: &OAuthToken, usage: &UsageResponse) {
     let plan = token
         .subscription_type
         .as_deref()
@@ -218,14 +258,19 @@ fn run() -> Result<()> {
     );
     println!("  {}", "─".repeat(65).dimmed());
 
-    let bar_width = 28;
-    print_window("5-hour session", &usage.five_hour, bar_width);
-    print_window("7-day rolling", &usage.seven_day, bar_width);
+    if settings.shows(Window::FiveHour) {
+        print_window("5-hour session", &usage.five_hour, settings);
+    }
+    if settings.shows(Window::SevenDay) {
+        print_window("7-day rolling", &usage.seven_day, settings);
+    }
 
     // Only show Opus row if it has data (Max plan)
-    if let Some(ref opus) = usage.seven_day_opus {
-        if opus.utilization > 0.0 || opus.resets_at.is_some() {
-            print_window("7-day (Opus)", &usage.seven_day_opus, bar_width);
+    if settings.shows(Window::Opus) {
+        if let Some(ref opus) = usage.seven_day_opus {
+            if opus.utilization > 0.0 || opus.resets_at.is_some() {
+                print_window("7-day (Opus)", &usage.seven_day_opus, settings);
+            }
         }
     }
 
@@ -237,12 +282,12 @@ fn run() -> Result<()> {
         .filter_map(|w| w.as_ref().map(|w| w.utilization))
         .fold(0.0_f64, f64::max);
 
-    if highest >= 90.0 {
+    if highest >= settings.critical_threshold {
         println!(
             "\n  {} You're nearly at your limit — check your reset time above.",
             "⚠".red().bold()
         );
-    } else if highest >= 70.0 {
+    } else if highest >= settings.elevated_threshold {
         println!(
             "\n  {} Usage is elevated — consider pacing your next session.",
             "△".yellow()
@@ -255,5 +300,56 @@ fn run() -> Result<()> {
     }
 
     println!();
-    Ok(())
+}
+
+/// Polls usage every `interval` seconds, redrawing the block in place until
+/// interrupted with Ctrl-C.
+fn watch(settings: &Settings, interval: u64) -> Result<()> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // A 0s interval would hammer the API with no sleep at all.
+    const MIN_INTERVAL_SECS: u64 = 1;
+    let interval = interval.max(MIN_INTERVAL_SECS);
+
+    let running = Arc::new(AtomicBool::new(true));
+    {
+        let running = running.clone();
+        ctrlc::set_handler(move || running.store(false, Ordering::SeqCst))
+            .context("Failed to install Ctrl-C handler")?;
+    }
+
+    let source = credentials::default_source();
+    let mut token = source.read_token()?;
+
+    print!("\x1b[?25l"); // hide cursor while redrawing in place
+
+    let result = (|| -> Result<()> {
+        while running.load(Ordering::SeqCst) {
+            let (usage, new_token) = fetch_usage_with_refresh(source.as_ref(), token)?;
+            token = new_token;
+
+            // Clear the screen and move the cursor home before redrawing,
+            // rather than letting each snapshot scroll the previous one away.
+            print!("\x1b[2J\x1b[H");
+            render_usage(settings, &token, &usage);
+            println!(
+                "  {} refreshing every {interval}s — press Ctrl-C to stop",
+                "↻".cyan()
+            );
+
+            for _ in 0..interval {
+                if !running.load(Ordering::SeqCst) {
+                    break;
+                }
+                std::thread::sleep(Duration::from_secs(1));
+            }
+        }
+        Ok(())
+    })();
+
+    print!("\x1b[?25h"); // restore cursor, even if the loop above bailed out
+    println!();
+    result
 }
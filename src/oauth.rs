@@ -0,0 +1,152 @@
+//! OAuth token refresh, mirroring the flow Claude Code itself uses to keep a
+//! session alive without requiring the user to log in again.
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::credentials::{CredentialSource, OAuthToken};
+
+const TOKEN_ENDPOINT: &str = "https://console.anthropic.com/v1/oauth/token";
+const CLIENT_ID: &str = "9d1c250a-e61b-44d9-88ed-5944d1962f5e";
+
+/// Marks an `anyhow::Error` as "the API said the token is unauthorized",
+/// so callers can distinguish it from other failures without string-matching.
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "token expired or invalid")
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+pub fn is_unauthorized(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<Unauthorized>().is_some()
+}
+
+#[derive(Debug, Serialize)]
+struct RefreshRequest<'a> {
+    grant_type: &'a str,
+    refresh_token: &'a str,
+    client_id: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct RefreshResponse {
+    access_token: String,
+    refresh_token: String,
+    expires_in: i64,
+}
+
+/// Exchanges `token`'s refresh token for a new access/refresh token pair and
+/// persists the result back to `source`. Callers should attempt this at most
+/// once per usage request — it does not retry internally.
+pub fn refresh(source: &dyn CredentialSource, token: &OAuthToken) -> Result<OAuthToken> {
+    let Some(refresh_token) = token.refresh_token.as_deref() else {
+        bail!("No refresh token available — log in again with `claude logout && claude`");
+    };
+
+    let client = Client::new();
+    let resp = client
+        .post(TOKEN_ENDPOINT)
+        .header("anthropic-beta", "oauth-2025-04-20")
+        .json(&RefreshRequest {
+            grant_type: "refresh_token",
+            refresh_token,
+            client_id: CLIENT_ID,
+        })
+        .send()
+        .context("Failed to reach Anthropic's OAuth token endpoint")?;
+
+    if !resp.status().is_success() {
+        bail!("Token refresh failed: {}", resp.status());
+    }
+
+    let refreshed: RefreshResponse = resp.json().context("Failed to parse refresh response")?;
+    let new_token = build_refreshed_token(token, refreshed);
+
+    // The refreshed token is good regardless of whether we can persist it —
+    // use it for this run even if the write-back fails (e.g. unsupported on
+    // this platform), rather than throwing away a valid refresh.
+    if let Err(e) = source.write_token(&new_token) {
+        eprintln!("  {} Could not save refreshed credentials: {e}", "warning:".yellow());
+    }
+    Ok(new_token)
+}
+
+/// Assembles the new token from a refresh response, carrying over whatever
+/// doesn't come back from the OAuth endpoint (e.g. `subscription_type`).
+fn build_refreshed_token(previous: &OAuthToken, refreshed: RefreshResponse) -> OAuthToken {
+    OAuthToken {
+        access_token: refreshed.access_token,
+        refresh_token: Some(refreshed.refresh_token),
+        expires_at: Some(Utc::now().timestamp_millis() + refreshed.expires_in * 1000),
+        subscription_type: previous.subscription_type.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(subscription_type: Option<&str>) -> OAuthToken {
+        OAuthToken {
+            access_token: "old-access".to_string(),
+            refresh_token: Some("old-refresh".to_string()),
+            expires_at: Some(0),
+            subscription_type: subscription_type.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn is_unauthorized_detects_the_marker_error() {
+        let err: anyhow::Error = Unauthorized.into();
+        assert!(is_unauthorized(&err));
+    }
+
+    #[test]
+    fn is_unauthorized_rejects_other_errors() {
+        let err = anyhow::anyhow!("network timeout");
+        assert!(!is_unauthorized(&err));
+    }
+
+    #[test]
+    fn build_refreshed_token_carries_over_subscription_type() {
+        let previous = token(Some("max"));
+        let refreshed = RefreshResponse {
+            access_token: "new-access".to_string(),
+            refresh_token: "new-refresh".to_string(),
+            expires_in: 3600,
+        };
+
+        let new_token = build_refreshed_token(&previous, refreshed);
+
+        assert_eq!(new_token.access_token, "new-access");
+        assert_eq!(new_token.refresh_token.as_deref(), Some("new-refresh"));
+        assert_eq!(new_token.subscription_type.as_deref(), Some("max"));
+    }
+
+    #[test]
+    fn build_refreshed_token_sets_expiry_from_expires_in() {
+        let previous = token(None);
+        let before = Utc::now().timestamp_millis();
+        let refreshed = RefreshResponse {
+            access_token: "new-access".to_string(),
+            refresh_token: "new-refresh".to_string(),
+            expires_in: 3600,
+        };
+
+        let new_token = build_refreshed_token(&previous, refreshed);
+        let after = Utc::now().timestamp_millis();
+
+        let expires_at = new_token.expires_at.expect("expires_at should be set");
+        assert!(expires_at >= before + 3600 * 1000);
+        assert!(expires_at <= after + 3600 * 1000);
+    }
+}